@@ -1,9 +1,16 @@
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, ValueEnum};
 use futures::stream::StreamExt;
 use hickory_resolver::TokioAsyncResolver;
+use hickory_resolver::config::{
+    NameServerConfig, NameServerConfigGroup, Protocol, ResolverConfig, ResolverOpts,
+};
 use hickory_resolver::error::ResolveErrorKind;
 use hickory_resolver::proto::op::ResponseCode;
-use std::net::IpAddr;
+use hickory_resolver::proto::rr::{RData, RecordType};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 
@@ -12,12 +19,12 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[command(
-    override_usage = "Required option missing: ruresol [OPTIONS] <-r|--reverse|-a|--address>"
+    override_usage = "Required option missing: ruresol [OPTIONS] <-r|--reverse|-a|--address|-T|--type <RTYPE>>"
 )]
 #[command(group(
     ArgGroup::new("mode")
         .required(true)
-        .args(["reverse", "address"])
+        .args(["reverse", "address", "record_type"])
 ))]
 struct Args {
     /// Reverse lookup mode (resolve IP to Hostname)
@@ -28,6 +35,10 @@ struct Args {
     #[arg(short = 'a', long)]
     address: bool,
 
+    /// Record type lookup mode (e.g. A, AAAA, MX, TXT, NS, SOA, CNAME, SRV, CAA, ANY)
+    #[arg(short = 'T', long = "type", value_name = "RTYPE")]
+    record_type: Option<RecordType>,
+
     /// Use IPv4 for address lookups (used with -a)
     #[arg(short = '4', long)]
     ipv4: bool,
@@ -52,24 +63,254 @@ struct Args {
     /// Output results as soon as they are ready (unordered), instead of preserving input order (default)
     #[arg(short = 'u', long)]
     unordered: bool,
+
+    /// Upstream nameserver to query instead of the system configuration.
+    /// Repeatable. For tls/https an optional `#sni-hostname` suffix sets the
+    /// TLS name (e.g. `1.1.1.1#cloudflare-dns.com`).
+    #[arg(short = 'n', long)]
+    nameserver: Vec<String>,
+
+    /// Transport protocol to use with `--nameserver`
+    #[arg(long, value_enum, default_value_t = TransportProtocol::Udp)]
+    protocol: TransportProtocol,
+
+    /// Output format for results
+    #[arg(short = 'o', long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Read input from a file instead of stdin
+    #[arg(short = 'f', long)]
+    file: Option<PathBuf>,
+
+    /// Minimum interval in milliseconds between released queries (0 = unthrottled)
+    #[arg(short = 'i', long, default_value_t = 0)]
+    interval: u64,
+
+    /// Interleave IPv4/IPv6 results when both families are queried (IPv4 first, or IPv6 first with --prefer-ipv6)
+    #[arg(long)]
+    happy_eyeballs: bool,
+
+    /// When interleaving, emit the first address from the IPv6 family (used with --happy-eyeballs)
+    #[arg(long)]
+    prefer_ipv6: bool,
+
+    /// On a negative answer, append the authoritative zone apex and its SOA minimum TTL
+    /// (text, json and jsonl output; csv rows do not include it)
+    #[arg(long)]
+    show_soa: bool,
+}
+
+/// How results are rendered at the sink.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable `input=a,b` / `input:STATUS` lines (default)
+    Text,
+    /// A single JSON array of result objects
+    Json,
+    /// One JSON object per line (streaming friendly)
+    Jsonl,
+    /// Comma-separated values with an `input,status,records` header
+    Csv,
+}
+
+/// Classification of a single lookup, independent of output format.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Ok,
+    NxDomain,
+    NoData,
+    TempError,
+    Invalid,
+}
+
+/// The address family a bare NODATA answer was for, retained so the Text
+/// output can reproduce the baseline `No A records found` / `No AAAA records
+/// found` wording. Not part of the serialized schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Family {
+    A,
+    Aaaa,
+}
+
+/// Authoritative zone information read from the SOA carried on a negative
+/// response, surfaced only when `--show-soa` is set.
+#[derive(Serialize, Clone, Debug)]
+struct SoaInfo {
+    zone: String,
+    minimum_ttl: u32,
+}
+
+/// Structured outcome of resolving one input line. The sink turns this into
+/// the requested `--output` representation.
+#[derive(Serialize, Debug)]
+struct LookupResult {
+    input: String,
+    status: Status,
+    records: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    soa: Option<SoaInfo>,
+    #[serde(skip)]
+    family: Option<Family>,
+}
+
+impl LookupResult {
+    fn ok(input: String, records: Vec<String>) -> Self {
+        LookupResult {
+            input,
+            status: Status::Ok,
+            records,
+            soa: None,
+            family: None,
+        }
+    }
+
+    /// A negative/empty outcome carrying no records.
+    fn empty(input: String, status: Status) -> Self {
+        LookupResult {
+            input,
+            status,
+            records: Vec::new(),
+            soa: None,
+            family: None,
+        }
+    }
+
+    /// Attach authoritative SOA information to a negative result.
+    fn with_soa(mut self, soa: Option<SoaInfo>) -> Self {
+        self.soa = soa;
+        self
+    }
+
+    /// Tag a NODATA result with the address family it was queried for, so the
+    /// Text output can render the family-specific message.
+    fn with_family(mut self, family: Option<Family>) -> Self {
+        self.family = family;
+        self
+    }
+
+    /// Render the original human-readable line form.
+    fn to_text(&self) -> String {
+        let base = match self.status {
+            Status::Ok => format!("{}={}", self.input, self.records.join(",")),
+            Status::NxDomain => format!("{}:NXDOMAIN", self.input),
+            Status::NoData => match self.family {
+                Some(Family::A) => format!("{}:No A records found", self.input),
+                Some(Family::Aaaa) => format!("{}:No AAAA records found", self.input),
+                None => format!("{}:No records found", self.input),
+            },
+            Status::TempError => format!("{}:Temporary error", self.input),
+            Status::Invalid => format!("{}:Invalid IP address format", self.input),
+        };
+        match &self.soa {
+            Some(soa) => format!("{} soa={}/{}", base, soa.zone, soa.minimum_ttl),
+            None => base,
+        }
+    }
+
+    /// Render a single CSV row (`input,status,records`). The record list is
+    /// joined with `;` so it stays within one CSV field.
+    fn to_csv_row(&self) -> String {
+        let status = serde_variant_name(self.status);
+        format!(
+            "{},{},{}",
+            csv_field(&self.input),
+            status,
+            csv_field(&self.records.join(";"))
+        )
+    }
+}
+
+/// The snake_case name used for a `Status` in serialized output.
+fn serde_variant_name(status: Status) -> &'static str {
+    match status {
+        Status::Ok => "ok",
+        Status::NxDomain => "nx_domain",
+        Status::NoData => "no_data",
+        Status::TempError => "temp_error",
+        Status::Invalid => "invalid",
+    }
+}
+
+/// Quote a CSV field if it contains a comma, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wire transport used when talking to a custom `--nameserver`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum TransportProtocol {
+    Udp,
+    Tcp,
+    Tls,
+    Https,
+}
+
+impl TransportProtocol {
+    /// The hickory `Protocol` this transport maps to.
+    fn protocol(self) -> Protocol {
+        match self {
+            TransportProtocol::Udp => Protocol::Udp,
+            TransportProtocol::Tcp => Protocol::Tcp,
+            TransportProtocol::Tls => Protocol::Tls,
+            TransportProtocol::Https => Protocol::Https,
+        }
+    }
+
+    /// Default port for the transport when the address omits one.
+    fn default_port(self) -> u16 {
+        match self {
+            TransportProtocol::Udp | TransportProtocol::Tcp => 53,
+            TransportProtocol::Tls => 853,
+            TransportProtocol::Https => 443,
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
-    // Initialize Resolver with Custom Timeout
-    let (config, mut opts) = hickory_resolver::system_conf::read_system_conf()?;
+    // Initialize Resolver with Custom Timeout. When one or more `--nameserver`
+    // options are given we bypass the system configuration entirely and build
+    // a ResolverConfig from the requested upstreams and transport.
+    let (config, mut opts) = if args.nameserver.is_empty() {
+        hickory_resolver::system_conf::read_system_conf()?
+    } else {
+        let group = build_nameserver_group(&args.nameserver, args.protocol)?;
+        (
+            ResolverConfig::from_parts(None, vec![], group),
+            ResolverOpts::default(),
+        )
+    };
     opts.timeout = Duration::from_millis(args.timeout);
     opts.attempts = args.attempts;
     let resolver = TokioAsyncResolver::tokio(config, opts);
 
-    // Setup Input Reading
-    let stdin = tokio::io::stdin();
-    let mut reader = BufReader::new(stdin);
+    // Setup Input Reading. Read from the given file, or stdin by default.
+    let source: Box<dyn tokio::io::AsyncRead + Unpin + Send> = match &args.file {
+        Some(path) => Box::new(tokio::fs::File::open(path).await?),
+        None => Box::new(tokio::io::stdin()),
+    };
+    let mut reader = BufReader::new(source);
+    let interval_ms = args.interval;
 
     // manual UTF-8 check instead of lines()
     let input_stream = async_stream::stream! {
+        // When an interval is set, release at most one query per tick. Delay
+        // missed ticks rather than bursting to catch up.
+        let mut ticker = if interval_ms > 0 {
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            Some(ticker)
+        } else {
+            None
+        };
+
         let mut buf = Vec::new();
         while let Ok(bytes_read) = reader.read_until(b'\n', &mut buf).await {
             if bytes_read == 0 { break; } // EOF
@@ -78,6 +319,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if let Ok(line_str) = std::str::from_utf8(&buf) {
                 let trimmed = line_str.trim().to_string();
                 if !trimmed.starts_with('#') && !trimmed.is_empty() {
+                    if let Some(ticker) = ticker.as_mut() {
+                        ticker.tick().await;
+                    }
                     yield trimmed;
                 }
             }
@@ -89,6 +333,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let tasks = input_stream.map(|input| {
         let resolver = resolver.clone();
         let do_reverse = args.reverse;
+        let record_type = args.record_type;
 
         let mut do_ipv4 = args.ipv4;
         let do_ipv6 = args.ipv6;
@@ -97,74 +342,154 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             do_ipv4 = true;
         }
 
-        async move { process_entry(input, resolver, do_reverse, do_ipv4, do_ipv6).await }
+        let happy_eyeballs = args.happy_eyeballs;
+        let prefer_ipv6 = args.prefer_ipv6;
+        let show_soa = args.show_soa;
+
+        async move {
+            process_entry(
+                input,
+                resolver,
+                record_type,
+                do_reverse,
+                do_ipv4,
+                do_ipv6,
+                happy_eyeballs,
+                prefer_ipv6,
+                show_soa,
+            )
+            .await
+        }
     });
 
-    // Execute with Concurrency Control
-    // We switch between buffered (ordered) and buffer_unordered (immediate)
-    if args.unordered {
-        tasks
-            .buffer_unordered(args.concurrency)
-            .for_each(|result| async move {
-                if let Some(output) = result {
-                    println!("{}", output);
-                }
-            })
-            .await;
+    // Execute with Concurrency Control.
+    // We switch between buffered (ordered) and buffer_unordered (immediate),
+    // boxing so both branches share the same sink below.
+    let mut results = if args.unordered {
+        tasks.buffer_unordered(args.concurrency).boxed()
     } else {
-        tasks
-            .buffered(args.concurrency)
-            .for_each(|result| async move {
-                if let Some(output) = result {
-                    println!("{}", output);
-                }
-            })
-            .await;
+        tasks.buffered(args.concurrency).boxed()
+    };
+
+    // Serialize each structured result according to the chosen format. `json`
+    // is the only format that must buffer the whole run (a single array); the
+    // others keep the line-at-a-time emission the concurrency model provides.
+    match args.output {
+        OutputFormat::Text => {
+            while let Some(result) = results.next().await {
+                println!("{}", result.to_text());
+            }
+        }
+        OutputFormat::Jsonl => {
+            while let Some(result) = results.next().await {
+                println!("{}", serde_json::to_string(&result)?);
+            }
+        }
+        OutputFormat::Csv => {
+            println!("input,status,records");
+            while let Some(result) = results.next().await {
+                println!("{}", result.to_csv_row());
+            }
+        }
+        OutputFormat::Json => {
+            let all: Vec<LookupResult> = results.collect().await;
+            println!("{}", serde_json::to_string(&all)?);
+        }
     }
 
     Ok(())
 }
 
+/// Build a `NameServerConfigGroup` from the `--nameserver` addresses and the
+/// chosen transport. Each address is `ip[:port][#sni-hostname]`; the port
+/// defaults to the transport's well-known port and the `#sni` suffix sets the
+/// TLS name for tls/https upstreams.
+fn build_nameserver_group(
+    addrs: &[String],
+    protocol: TransportProtocol,
+) -> Result<NameServerConfigGroup, Box<dyn std::error::Error>> {
+    let mut group = NameServerConfigGroup::new();
+
+    for addr in addrs {
+        let (host, tls_name) = match addr.split_once('#') {
+            Some((host, sni)) => (host, Some(sni.to_string())),
+            None => (addr.as_str(), None),
+        };
+
+        // Accept either a bare IP or a full `ip:port`.
+        let socket_addr = if let Ok(ip) = host.parse::<IpAddr>() {
+            SocketAddr::new(ip, protocol.default_port())
+        } else {
+            host.parse::<SocketAddr>()
+                .map_err(|_| format!("Invalid nameserver address: {}", addr))?
+        };
+
+        let mut nsc = NameServerConfig::new(socket_addr, protocol.protocol());
+        nsc.tls_dns_name = tls_name;
+        group.push(nsc);
+    }
+
+    Ok(group)
+}
+
 async fn process_entry(
     input: String,
     resolver: TokioAsyncResolver,
+    record_type: Option<RecordType>,
     do_reverse: bool,
     do_ipv4: bool,
     do_ipv6: bool,
-) -> Option<String> {
-    if do_reverse {
+    happy_eyeballs: bool,
+    prefer_ipv6: bool,
+    show_soa: bool,
+) -> LookupResult {
+    if let Some(rtype) = record_type {
+        // Mode: Arbitrary record type lookup (e.g. MX, TXT, NS, SOA, SRV, CAA)
+        match resolver.lookup(&input, rtype).await {
+            Ok(lookup) => {
+                let results: Vec<String> = lookup.iter().map(format_rdata).collect();
+                if results.is_empty() {
+                    return LookupResult::empty(input, Status::NoData);
+                }
+                LookupResult::ok(input, results)
+            }
+            Err(e) => {
+                let soa = show_soa.then(|| extract_soa(&e)).flatten();
+                let status = classify_error(&e, Status::NoData, Status::TempError);
+                LookupResult::empty(input, status).with_soa(soa)
+            }
+        }
+    } else if do_reverse {
         // Mode: Reverse Lookup (IP -> Hostname)
         if let Ok(ip) = input.parse::<IpAddr>() {
             match resolver.reverse_lookup(ip).await {
                 Ok(lookup) => {
                     if let Some(name) = lookup.iter().next() {
-                        return Some(format!("{}={}", input, name));
+                        return LookupResult::ok(input, vec![name.to_string()]);
                     }
-                    Some(format!("{}:No records found", input))
+                    LookupResult::empty(input, Status::NoData)
+                }
+                Err(e) => {
+                    let soa = show_soa.then(|| extract_soa(&e)).flatten();
+                    let status = classify_error(&e, Status::NoData, Status::NoData);
+                    LookupResult::empty(input, status).with_soa(soa)
                 }
-                Err(e) => match e.kind() {
-                    ResolveErrorKind::NoRecordsFound { response_code, .. } => match response_code {
-                        ResponseCode::NXDomain => Some(format!("{}:NXDOMAIN", input)),
-                        ResponseCode::ServFail => Some(format!("{}:Temporary error", input)),
-                        _ => Some(format!("{}:No records found", input)),
-                    },
-                    ResolveErrorKind::Timeout => Some(format!("{}:Temporary error", input)),
-                    _ => Some(format!("{}:Temporary error", input)),
-                },
             }
         } else {
-            Some(format!("{}:Invalid IP address format", input))
+            LookupResult::empty(input, Status::Invalid)
         }
     } else {
-        // Mode: Forward Lookup (Hostname -> IP)
-        let mut results = Vec::new();
+        // Mode: Forward Lookup (Hostname -> IP). Keep the two families separate
+        // so they can be interleaved per RFC 8305 when requested.
+        let mut v4_results = Vec::new();
+        let mut v6_results = Vec::new();
         let mut errors = Vec::new();
 
         if do_ipv4 {
             match resolver.ipv4_lookup(&input).await {
                 Ok(lookup) => {
                     for ip in lookup.iter() {
-                        results.push(ip.to_string());
+                        v4_results.push(ip.to_string());
                     }
                 }
                 Err(e) => errors.push(e),
@@ -175,23 +500,41 @@ async fn process_entry(
             match resolver.ipv6_lookup(&input).await {
                 Ok(lookup) => {
                     for ip in lookup.iter() {
-                        results.push(ip.to_string());
+                        v6_results.push(ip.to_string());
                     }
                 }
                 Err(e) => errors.push(e),
             }
         }
 
+        // Interleave the families when both were queried and happy-eyeballs is
+        // on (RFC 8305 style one-from-each ordering); otherwise keep the
+        // historical all-v4-then-all-v6 concatenation.
+        let results = if happy_eyeballs && do_ipv4 && do_ipv6 {
+            interleave(v4_results, v6_results, prefer_ipv6)
+        } else {
+            let mut combined = v4_results;
+            combined.extend(v6_results);
+            combined
+        };
+
         // If we found any records, return them (Success)
         if !results.is_empty() {
-            return Some(format!("{}={}", input, results.join(",")));
+            return LookupResult::ok(input, results);
         }
 
-        // If no results, analyze errors to determine the message
+        // If no results, analyze errors to determine the status
         if errors.is_empty() {
-            return Some(format!("{}:No records found", input));
+            return LookupResult::empty(input, Status::NoData);
         }
 
+        // Pull the authoritative SOA off the first error that carries one.
+        let soa = if show_soa {
+            errors.iter().find_map(extract_soa)
+        } else {
+            None
+        };
+
         // Check Error Priority: NXDOMAIN > Temporary > NODATA
         let mut has_nxdomain = false;
         let mut has_temp_error = false;
@@ -212,25 +555,209 @@ async fn process_entry(
         }
 
         if has_nxdomain {
-            return Some(format!("{}:NXDOMAIN", input));
+            return LookupResult::empty(input, Status::NxDomain).with_soa(soa);
         }
 
         if has_temp_error {
-            return Some(format!("{}:Temporary error", input));
+            return LookupResult::empty(input, Status::TempError).with_soa(soa);
         }
 
         // If we are here, we only had NoRecordsFound with NoError (NODATA).
-        if do_ipv4 && !do_ipv6 {
-            Some(format!("{}:No A records found", input))
+        // Retain the queried family so Text output keeps the baseline's
+        // family-specific wording.
+        let family = if do_ipv4 && !do_ipv6 {
+            Some(Family::A)
         } else if do_ipv6 && !do_ipv4 {
-            Some(format!("{}:No AAAA records found", input))
+            Some(Family::Aaaa)
+        } else {
+            None
+        };
+        LookupResult::empty(input, Status::NoData)
+            .with_soa(soa)
+            .with_family(family)
+    }
+}
+
+/// Read the authoritative zone apex and SOA minimum TTL from the authority
+/// section carried on a `NoRecordsFound` error, if present.
+fn extract_soa(e: &hickory_resolver::error::ResolveError) -> Option<SoaInfo> {
+    if let ResolveErrorKind::NoRecordsFound { soa, .. } = e.kind() {
+        if let Some(record) = soa {
+            return Some(SoaInfo {
+                zone: record.name().to_string(),
+                minimum_ttl: record.data().map(|data| data.minimum()).unwrap_or(0),
+            });
+        }
+    }
+    None
+}
+
+/// Interleave the IPv4 and IPv6 result lists in the one-from-each-family style
+/// of RFC 8305: emit one address from each family in turn, starting with the
+/// preferred family (IPv4 by default, IPv6 when `prefer_ipv6` is set), toggling
+/// after each pop and skipping a family once it is exhausted.
+fn interleave(v4: Vec<String>, v6: Vec<String>, prefer_ipv6: bool) -> Vec<String> {
+    let (mut preferred, mut other): (VecDeque<String>, VecDeque<String>) = if prefer_ipv6 {
+        (v6.into(), v4.into())
+    } else {
+        (v4.into(), v6.into())
+    };
+
+    let mut out = Vec::with_capacity(preferred.len() + other.len());
+    let mut take_preferred = true;
+    while !preferred.is_empty() || !other.is_empty() {
+        if take_preferred {
+            if let Some(addr) = preferred.pop_front() {
+                out.push(addr);
+            }
+            if !other.is_empty() {
+                take_preferred = false;
+            }
         } else {
-            Some(format!("{}:No records found", input))
+            if let Some(addr) = other.pop_front() {
+                out.push(addr);
+            }
+            if !preferred.is_empty() {
+                take_preferred = true;
+            }
         }
     }
+    out
+}
+
+/// Classify a single `ResolveError` into a `Status`. `noerror_default` is the
+/// status used for a genuine NODATA (`NoError`) empty response; `other_default`
+/// is used for response codes we don't specifically recognise. These differ by
+/// caller: the reverse path historically treated unknown codes as NODATA,
+/// whereas the record-type path treated them as a temporary error.
+fn classify_error(
+    e: &hickory_resolver::error::ResolveError,
+    noerror_default: Status,
+    other_default: Status,
+) -> Status {
+    match e.kind() {
+        ResolveErrorKind::NoRecordsFound { response_code, .. } => match response_code {
+            ResponseCode::NXDomain => Status::NxDomain,
+            ResponseCode::ServFail => Status::TempError,
+            ResponseCode::NoError => noerror_default,
+            _ => other_default,
+        },
+        ResolveErrorKind::Timeout => Status::TempError,
+        _ => Status::TempError,
+    }
+}
+
+/// Render a single `RData` into the compact, comma-join-friendly value form
+/// used by the `input=value1,value2` output layout.
+fn format_rdata(rdata: &RData) -> String {
+    match rdata {
+        RData::MX(mx) => format!("{} {}", mx.preference(), mx.exchange()),
+        RData::SRV(srv) => format!(
+            "{} {} {} {}",
+            srv.priority(),
+            srv.weight(),
+            srv.port(),
+            srv.target()
+        ),
+        RData::SOA(soa) => format!(
+            "{} {} {} {} {} {} {}",
+            soa.mname(),
+            soa.rname(),
+            soa.serial(),
+            soa.refresh(),
+            soa.retry(),
+            soa.expire(),
+            soa.minimum()
+        ),
+        // TXT records carry one or more character-strings; concatenate them
+        // the way most tooling presents the record as a single value.
+        RData::TXT(txt) => txt
+            .txt_data()
+            .iter()
+            .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+            .collect::<Vec<_>>()
+            .join(""),
+        other => other.to_string(),
+    }
 }
 
 // Helper dependency for the stream macro
 mod async_stream {
     pub use async_stream::stream;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn interleave_alternates_v4_first_by_default() {
+        let out = interleave(v(&["1.1.1.1", "2.2.2.2"]), v(&["::1", "::2"]), false);
+        assert_eq!(out, v(&["1.1.1.1", "::1", "2.2.2.2", "::2"]));
+    }
+
+    #[test]
+    fn interleave_prefers_v6_when_requested() {
+        let out = interleave(v(&["1.1.1.1", "2.2.2.2"]), v(&["::1", "::2"]), true);
+        assert_eq!(out, v(&["::1", "1.1.1.1", "::2", "2.2.2.2"]));
+    }
+
+    #[test]
+    fn interleave_drains_longer_family_after_shorter_exhausts() {
+        let out = interleave(v(&["1.1.1.1"]), v(&["::1", "::2", "::3"]), false);
+        assert_eq!(out, v(&["1.1.1.1", "::1", "::2", "::3"]));
+    }
+
+    #[test]
+    fn interleave_handles_empty_families() {
+        assert_eq!(interleave(v(&[]), v(&["::1"]), false), v(&["::1"]));
+        assert_eq!(interleave(v(&["1.1.1.1"]), v(&[]), true), v(&["1.1.1.1"]));
+        assert!(interleave(v(&[]), v(&[]), false).is_empty());
+    }
+
+    #[test]
+    fn nameserver_bare_ip_uses_transport_default_port() {
+        let group =
+            build_nameserver_group(&["8.8.8.8".to_string()], TransportProtocol::Udp).unwrap();
+        assert_eq!(group.len(), 1);
+        assert_eq!(group[0].socket_addr.port(), 53);
+        assert_eq!(group[0].protocol, Protocol::Udp);
+        assert_eq!(group[0].tls_dns_name, None);
+    }
+
+    #[test]
+    fn nameserver_explicit_port_is_preserved() {
+        let group =
+            build_nameserver_group(&["127.0.0.1:5353".to_string()], TransportProtocol::Tcp)
+                .unwrap();
+        assert_eq!(group[0].socket_addr.port(), 5353);
+        assert_eq!(group[0].protocol, Protocol::Tcp);
+    }
+
+    #[test]
+    fn nameserver_sni_suffix_sets_tls_name_and_default_port() {
+        let group = build_nameserver_group(
+            &["1.1.1.1#cloudflare-dns.com".to_string()],
+            TransportProtocol::Https,
+        )
+        .unwrap();
+        assert_eq!(group[0].socket_addr.port(), 443);
+        assert_eq!(group[0].protocol, Protocol::Https);
+        assert_eq!(
+            group[0].tls_dns_name.as_deref(),
+            Some("cloudflare-dns.com")
+        );
+    }
+
+    #[test]
+    fn nameserver_rejects_garbage() {
+        assert!(
+            build_nameserver_group(&["not an address".to_string()], TransportProtocol::Udp)
+                .is_err()
+        );
+    }
+}